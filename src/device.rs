@@ -31,15 +31,46 @@ pub enum SongSlot {
     Fourth = 3,
 }
 
-/// Represents the duration of a single note in ticks of 1/64 seconds. You can construct an
-/// instance of this structure using the U16Ext trait for u16 in the prelude:
+/// Represents the duration of a single note. The Rumba timebase is 1/64 second
+/// ticks, but the requested duration is kept in exact milliseconds internally and
+/// only rounded to the nearest tick when serialized, so no sub-tick precision is
+/// lost across conversions. You can construct an instance using the U16Ext trait
+/// for u16 in the prelude:
 /// ```rust
 /// use rumba::{NoteDuration, prelude::*};
 ///
 /// let duration: NoteDuration = 64u16.ms();
 /// ```
 pub struct NoteDuration {
-    ticks: u8,
+    ms: u16,
+}
+
+/// The requested note duration is longer than the 255-tick (~3.98 s) slot limit.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DurationTooLong;
+
+impl NoteDuration {
+    /// Constructs a duration from milliseconds, reporting an error if the note is
+    /// longer than the 255-tick hardware limit rather than silently saturating.
+    pub fn from_ms(ms: u16) -> Result<NoteDuration, DurationTooLong> {
+        let duration = NoteDuration { ms };
+        if duration.ticks_rounded() > 255 {
+            Err(DurationTooLong)
+        } else {
+            Ok(duration)
+        }
+    }
+
+    /// Exact number of 1/64 s ticks, rounded to nearest, without saturation.
+    fn ticks_rounded(&self) -> u32 {
+        (self.ms as u32 * 64 + 500) / 1000
+    }
+
+    /// Number of 1/64 s ticks sent over the wire, rounded to nearest and
+    /// saturated at the 255-tick maximum the protocol can express.
+    fn ticks(&self) -> u8 {
+        self.ticks_rounded().min(255) as u8
+    }
 }
 
 /// Common traits for the Rumba. This includes an extension for u16 to convert it into milliseconds
@@ -53,9 +84,47 @@ pub mod prelude {
 
     impl U16Ext for u16 {
         fn ms(self) -> NoteDuration {
-            let ticks = (self as u64 * 64 / 1000) as u8;
-            NoteDuration { ticks }
+            NoteDuration { ms: self }
+        }
+    }
+}
+
+/// Helpers to convert between note frequencies and MIDI note numbers.
+pub mod note {
+    use super::{NoteName, NoteOctave};
+
+    /// Frequencies, in milli-hertz, of the twelve semitones of MIDI octave -1
+    /// (MIDI numbers 0..=11, C-1 through B-1). Higher octaves are obtained by
+    /// doubling, which is an exact left shift.
+    const SEMITONE_MILLIHERTZ: [u32; 12] = [
+        8176, 8662, 9177, 9723, 10301, 10913, 11562, 12250, 12978, 13750, 14568, 15434,
+    ];
+
+    /// MIDI note number for a note name at a given octave.
+    pub fn midi(name: NoteName, octave: NoteOctave) -> u8 {
+        name as u8 + octave as u8
+    }
+
+    /// Frequency, in milli-hertz, of the given MIDI note number.
+    pub fn frequency_mhz(midi: u8) -> u32 {
+        SEMITONE_MILLIHERTZ[(midi % 12) as usize] << (midi / 12)
+    }
+
+    /// MIDI note number whose frequency is closest to `hz`.
+    pub fn from_frequency(hz: u16) -> u8 {
+        let target = hz as u32 * 1000;
+        let mut best = 0;
+        let mut best_diff = u32::MAX;
+        let mut midi = 0;
+        while midi < 128 {
+            let diff = frequency_mhz(midi).abs_diff(target);
+            if diff < best_diff {
+                best_diff = diff;
+                best = midi;
+            }
+            midi += 1;
         }
+        best
     }
 }
 
@@ -100,7 +169,7 @@ pub struct Note {
 
 impl Note {
     fn duration(&self) -> u8 {
-        self.duration.ticks
+        self.duration.ticks()
     }
 
     fn midi_value(&self) -> u8 {
@@ -108,6 +177,145 @@ impl Note {
     }
 }
 
+/// Sensor packets that can be queried from the Rumba.
+///
+/// Each variant carries the packet ID defined by the OI spec as its discriminant
+/// so it can be written directly over the wire. The number of data bytes the
+/// robot replies with is fixed per packet and reported by [`PacketId::byte_len`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PacketId {
+    BumpsAndWheelDrops = 7,
+    Distance = 19,
+    Angle = 20,
+    ChargingState = 21,
+    Voltage = 22,
+    BatteryCharge = 25,
+}
+
+impl PacketId {
+    /// Reconstructs a packet identifier from its raw wire value, if known.
+    pub fn from_id(id: u8) -> Option<PacketId> {
+        match id {
+            7 => Some(PacketId::BumpsAndWheelDrops),
+            19 => Some(PacketId::Distance),
+            20 => Some(PacketId::Angle),
+            21 => Some(PacketId::ChargingState),
+            22 => Some(PacketId::Voltage),
+            25 => Some(PacketId::BatteryCharge),
+            _ => None,
+        }
+    }
+
+    /// Number of data bytes the robot returns for this packet.
+    pub const fn byte_len(self) -> usize {
+        match self {
+            PacketId::BumpsAndWheelDrops | PacketId::ChargingState => 1,
+            PacketId::Distance
+            | PacketId::Angle
+            | PacketId::Voltage
+            | PacketId::BatteryCharge => 2,
+        }
+    }
+
+    /// Decodes the raw payload returned for this packet into typed data.
+    ///
+    /// Multi-byte values arrive high byte first (big-endian) and signed packets
+    /// are interpreted as two's complement.
+    fn decode(self, bytes: &[u8]) -> SensorData {
+        match self {
+            PacketId::BumpsAndWheelDrops => SensorData::BumpsAndWheelDrops(bytes[0]),
+            PacketId::ChargingState => SensorData::ChargingState(bytes[0]),
+            PacketId::Distance => SensorData::Distance(i16::from_be_bytes([bytes[0], bytes[1]])),
+            PacketId::Angle => SensorData::Angle(i16::from_be_bytes([bytes[0], bytes[1]])),
+            PacketId::Voltage => SensorData::Voltage(u16::from_be_bytes([bytes[0], bytes[1]])),
+            PacketId::BatteryCharge => {
+                SensorData::BatteryCharge(u16::from_be_bytes([bytes[0], bytes[1]]))
+            }
+        }
+    }
+}
+
+/// Decoded value of a single sensor packet.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SensorData {
+    /// Bump and wheel-drop bitfield (packet 7).
+    BumpsAndWheelDrops(u8),
+    /// Distance travelled in millimetres since the last read (packet 19).
+    Distance(i16),
+    /// Angle turned in degrees since the last read (packet 20).
+    Angle(i16),
+    /// Charging-state code (packet 21).
+    ChargingState(u8),
+    /// Battery voltage in millivolts (packet 22).
+    Voltage(u16),
+    /// Current battery charge in milliamp-hours (packet 25).
+    BatteryCharge(u16),
+}
+
+/// Error returned by the actuator commands.
+pub enum ActuatorError<E> {
+    /// A velocity, radius or PWM argument was outside the range allowed by the spec.
+    OutOfRange,
+    /// The underlying serial write failed.
+    Write(E),
+}
+
+/// Maximum number of notes a single song slot can hold.
+pub const MAX_SONG_NOTES: usize = 16;
+
+/// Maximum number of packet ids accepted in a single query-list (opcode 149) or
+/// stream (opcode 148) request, bounded by the 35-byte command buffer those
+/// commands are serialized into (`35 - opcode - count`).
+pub const MAX_PACKET_LIST: usize = 33;
+
+/// Error returned when uploading a song.
+pub enum SongError<E> {
+    /// The song has more than [`MAX_SONG_NOTES`] notes and would overrun the slot.
+    TooManyNotes,
+    /// The underlying serial write failed.
+    Write(E),
+}
+
+/// Error returned when subscribing to a sensor stream.
+pub enum StreamError<E> {
+    /// The subscription lists more than [`MAX_PACKET_LIST`] packets and would
+    /// overrun the command buffer.
+    TooManyPackets,
+    /// The underlying serial write failed.
+    Write(E),
+}
+
+/// Error returned by the fallible Rumba operations that both read and write.
+pub enum Error<T>
+where
+    T: Read<u8> + Write<u8>,
+{
+    /// The underlying serial read failed.
+    Read(<T as Read<u8>>::Error),
+    /// The underlying serial write failed.
+    Write(<T as Write<u8>>::Error),
+    /// The request listed more than [`MAX_PACKET_LIST`] packets.
+    TooManyPackets,
+}
+
+/// Serial ports whose transmit and receive halves can be separated.
+///
+/// This mirrors the common UART `split`/`reunite` pattern and lets the driver be
+/// divided into an independent command ([`RumbaTx`]) and sensor ([`RumbaRx`])
+/// half so the two can live in different execution contexts.
+pub trait SplitSerial: Read<u8> + Write<u8> {
+    /// Transmit half owning the command channel.
+    type Tx: Write<u8>;
+    /// Receive half owning the sensor channel.
+    type Rx: Read<u8>;
+
+    /// Splits the port into its transmit and receive halves.
+    fn split(self) -> (Self::Tx, Self::Rx);
+
+    /// Recombines the two halves back into a whole port.
+    fn reunite(tx: Self::Tx, rx: Self::Rx) -> Self;
+}
+
 /// Representation of a Roomba instance.
 pub struct Rumba<T: Read<u8> + Write<u8>, MODE> {
     io_port: Option<T>,
@@ -165,7 +373,10 @@ where
         &mut self,
         song: SongSlot,
         notes: &[Note],
-    ) -> Result<(), <T as Write<u8>>::Error> {
+    ) -> Result<(), SongError<<T as Write<u8>>::Error>> {
+        if notes.len() > MAX_SONG_NOTES {
+            return Err(SongError::TooManyNotes);
+        }
         let mut buffer = [0; 35];
         buffer[0] = 140;
         buffer[1] = song as u8;
@@ -174,8 +385,8 @@ where
             buffer[3 + index * 2] = element.midi_value();
             buffer[4 + index * 2] = element.duration();
         }
-        self.write(&buffer[..3 + 2 * notes.len()])?;
-        Ok(())
+        self.write(&buffer[..3 + 2 * notes.len()])
+            .map_err(SongError::Write)
     }
 
     /// Starts/stops the cleaning mode in Rumba
@@ -189,6 +400,38 @@ where
         self.write(&[136])?;
         Ok(())
     }
+
+    /// Reads the state of a single sensor packet (opcode 142)
+    pub fn sensor(&mut self, packet_id: PacketId) -> Result<SensorData, Error<T>> {
+        self.query(packet_id)
+    }
+
+    /// Reads several sensor packets in one round trip (opcode 149)
+    pub fn sensors(
+        &mut self,
+        packet_ids: &[PacketId],
+        out: &mut [SensorData],
+    ) -> Result<(), Error<T>> {
+        self.query_list(packet_ids, out)
+    }
+
+    /// Subscribes to a background sensor stream (opcode 148)
+    pub fn stream(
+        &mut self,
+        packet_ids: &[PacketId],
+    ) -> Result<(), StreamError<<T as Write<u8>>::Error>> {
+        self.start_stream(packet_ids)
+    }
+
+    /// Pauses the background sensor stream (opcode 150)
+    pub fn pause(&mut self) -> Result<(), <T as Write<u8>>::Error> {
+        self.pause_stream()
+    }
+
+    /// Resumes the background sensor stream (opcode 150)
+    pub fn resume(&mut self) -> Result<(), <T as Write<u8>>::Error> {
+        self.resume_stream()
+    }
 }
 
 impl<T> Rumba<T, mode::Safe>
@@ -201,6 +444,101 @@ where
         Ok(())
     }
 
+    /// Drives the robot along an arc (opcode 137)
+    pub fn drive(
+        &mut self,
+        velocity_mm_s: i16,
+        radius_mm: i16,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        self.send_drive(velocity_mm_s, radius_mm)
+    }
+
+    /// Drives each wheel independently (opcode 145)
+    pub fn drive_direct(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        self.send_drive_direct(right, left)
+    }
+
+    /// Drives each wheel by raw PWM (opcode 146)
+    pub fn drive_pwm(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        self.send_drive_pwm(right, left)
+    }
+
+    /// Switches the cleaning motors on or off (opcode 138)
+    pub fn motors(
+        &mut self,
+        main: bool,
+        side: bool,
+        vacuum: bool,
+    ) -> Result<(), <T as Write<u8>>::Error> {
+        self.send_motors(main, side, vacuum)
+    }
+
+    /// Sets the LED bitfield and power LED colour/intensity (opcode 139)
+    pub fn leds(
+        &mut self,
+        bits: u8,
+        power_color: u8,
+        power_intensity: u8,
+    ) -> Result<(), <T as Write<u8>>::Error> {
+        self.send_leds(bits, power_color, power_intensity)
+    }
+
+    /// Sets the four seven-segment digits from ASCII characters (opcode 164)
+    pub fn digit_leds_ascii(&mut self, digits: [u8; 4]) -> Result<(), <T as Write<u8>>::Error> {
+        self.send_digit_leds_ascii(digits)
+    }
+
+    /// Reads the state of a single sensor packet (opcode 142)
+    pub fn sensor(&mut self, packet_id: PacketId) -> Result<SensorData, Error<T>> {
+        self.query(packet_id)
+    }
+
+    /// Reads several sensor packets in one round trip (opcode 149)
+    pub fn sensors(
+        &mut self,
+        packet_ids: &[PacketId],
+        out: &mut [SensorData],
+    ) -> Result<(), Error<T>> {
+        self.query_list(packet_ids, out)
+    }
+
+    /// Subscribes to a background sensor stream (opcode 148)
+    pub fn stream(
+        &mut self,
+        packet_ids: &[PacketId],
+    ) -> Result<(), StreamError<<T as Write<u8>>::Error>> {
+        self.start_stream(packet_ids)
+    }
+
+    /// Pauses the background sensor stream (opcode 150)
+    pub fn pause(&mut self) -> Result<(), <T as Write<u8>>::Error> {
+        self.pause_stream()
+    }
+
+    /// Resumes the background sensor stream (opcode 150)
+    pub fn resume(&mut self) -> Result<(), <T as Write<u8>>::Error> {
+        self.resume_stream()
+    }
+
+    /// Switches to the Full state
+    pub fn into_full(mut self) -> Rumba<T, mode::Full> {
+        if let Err(_error) = self.write(&[132]) {
+            panic!("Error entering the full state failed!");
+        }
+        Rumba {
+            io_port: Some(self.decompose()),
+            _mode: PhantomData,
+        }
+    }
+
     /// Switches to the Off state
     pub fn into_off(mut self) -> Rumba<T, mode::Off> {
         self.enter_off_state();
@@ -220,6 +558,144 @@ where
     }
 }
 
+impl<T> Rumba<T, mode::Full>
+where
+    T: Read<u8> + Write<u8>,
+{
+    /// Plays the specified song
+    pub fn play_song(&mut self, song: SongSlot) -> Result<(), <T as Write<u8>>::Error> {
+        self.write(&[141, song as u8])?;
+        Ok(())
+    }
+
+    /// Drives the robot along an arc (opcode 137)
+    pub fn drive(
+        &mut self,
+        velocity_mm_s: i16,
+        radius_mm: i16,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        self.send_drive(velocity_mm_s, radius_mm)
+    }
+
+    /// Drives each wheel independently (opcode 145)
+    pub fn drive_direct(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        self.send_drive_direct(right, left)
+    }
+
+    /// Drives each wheel by raw PWM (opcode 146)
+    pub fn drive_pwm(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        self.send_drive_pwm(right, left)
+    }
+
+    /// Switches the cleaning motors on or off (opcode 138)
+    pub fn motors(
+        &mut self,
+        main: bool,
+        side: bool,
+        vacuum: bool,
+    ) -> Result<(), <T as Write<u8>>::Error> {
+        self.send_motors(main, side, vacuum)
+    }
+
+    /// Sets the LED bitfield and power LED colour/intensity (opcode 139)
+    pub fn leds(
+        &mut self,
+        bits: u8,
+        power_color: u8,
+        power_intensity: u8,
+    ) -> Result<(), <T as Write<u8>>::Error> {
+        self.send_leds(bits, power_color, power_intensity)
+    }
+
+    /// Sets the four seven-segment digits from ASCII characters (opcode 164)
+    pub fn digit_leds_ascii(&mut self, digits: [u8; 4]) -> Result<(), <T as Write<u8>>::Error> {
+        self.send_digit_leds_ascii(digits)
+    }
+
+    /// Drives the cleaning motors by raw PWM (opcode 144).
+    ///
+    /// Unlike [`motors`](Self::motors), which only toggles them, this exposes the
+    /// low-level PWM duty cycles. It is offered only in Full mode, the mode that
+    /// grants complete, unsupervised control over the actuators.
+    pub fn motors_pwm(
+        &mut self,
+        main: i8,
+        side: i8,
+        vacuum: u8,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        self.send_motors_pwm(main, side, vacuum)
+    }
+
+    /// Reads the state of a single sensor packet (opcode 142)
+    pub fn sensor(&mut self, packet_id: PacketId) -> Result<SensorData, Error<T>> {
+        self.query(packet_id)
+    }
+
+    /// Reads several sensor packets in one round trip (opcode 149)
+    pub fn sensors(
+        &mut self,
+        packet_ids: &[PacketId],
+        out: &mut [SensorData],
+    ) -> Result<(), Error<T>> {
+        self.query_list(packet_ids, out)
+    }
+
+    /// Subscribes to a background sensor stream (opcode 148)
+    pub fn stream(
+        &mut self,
+        packet_ids: &[PacketId],
+    ) -> Result<(), StreamError<<T as Write<u8>>::Error>> {
+        self.start_stream(packet_ids)
+    }
+
+    /// Pauses the background sensor stream (opcode 150)
+    pub fn pause(&mut self) -> Result<(), <T as Write<u8>>::Error> {
+        self.pause_stream()
+    }
+
+    /// Resumes the background sensor stream (opcode 150)
+    pub fn resume(&mut self) -> Result<(), <T as Write<u8>>::Error> {
+        self.resume_stream()
+    }
+
+    /// Switches to the Safe state
+    pub fn into_safe(mut self) -> Rumba<T, mode::Safe> {
+        if let Err(_error) = self.write(&[131]) {
+            panic!("Error entering the safe state failed!");
+        }
+        Rumba {
+            io_port: Some(self.decompose()),
+            _mode: PhantomData,
+        }
+    }
+
+    /// Switches to the Passive state
+    pub fn into_passive(mut self) -> Rumba<T, mode::Passive> {
+        self.enter_passive_state();
+        Rumba {
+            io_port: Some(self.decompose()),
+            _mode: PhantomData,
+        }
+    }
+
+    /// Switches to the Off state
+    pub fn into_off(mut self) -> Rumba<T, mode::Off> {
+        self.enter_off_state();
+        Rumba {
+            io_port: Some(self.decompose()),
+            _mode: PhantomData,
+        }
+    }
+}
+
 impl<T, MODE> Rumba<T, MODE>
 where
     T: Read<u8> + Write<u8>,
@@ -231,6 +707,177 @@ where
         Ok(())
     }
 
+    /// Reads exactly `len` bytes from the port into the front of `buffer`.
+    fn read_exact(&mut self, buffer: &mut [u8], len: usize) -> Result<(), Error<T>> {
+        for slot in buffer.iter_mut().take(len) {
+            *slot = nb::block!(self.io_port.as_mut().unwrap().read()).map_err(Error::Read)?;
+        }
+        Ok(())
+    }
+
+    /// Queries a single sensor packet using opcode 142.
+    fn query(&mut self, packet_id: PacketId) -> Result<SensorData, Error<T>> {
+        self.write(&[142, packet_id as u8]).map_err(Error::Write)?;
+        let mut buffer = [0; 2];
+        self.read_exact(&mut buffer, packet_id.byte_len())?;
+        Ok(packet_id.decode(&buffer[..packet_id.byte_len()]))
+    }
+
+    /// Queries several sensor packets in a single round trip using opcode 149.
+    ///
+    /// The decoded values are written into `out` in the same order as
+    /// `packet_ids`; `out` must be at least as long as `packet_ids`.
+    fn query_list(
+        &mut self,
+        packet_ids: &[PacketId],
+        out: &mut [SensorData],
+    ) -> Result<(), Error<T>> {
+        if packet_ids.len() > MAX_PACKET_LIST {
+            return Err(Error::TooManyPackets);
+        }
+        let mut header = [0; 35];
+        header[0] = 149;
+        header[1] = packet_ids.len() as u8;
+        for (index, id) in packet_ids.iter().enumerate() {
+            header[2 + index] = *id as u8;
+        }
+        self.write(&header[..2 + packet_ids.len()])
+            .map_err(Error::Write)?;
+        let mut buffer = [0; 2];
+        for (id, slot) in packet_ids.iter().zip(out.iter_mut()) {
+            self.read_exact(&mut buffer, id.byte_len())?;
+            *slot = id.decode(&buffer[..id.byte_len()]);
+        }
+        Ok(())
+    }
+
+    /// Subscribes to a background stream of sensor packets (opcode 148).
+    ///
+    /// After this call the robot pushes a frame of the requested packets every
+    /// 15 ms; decode them with a [`stream::StreamConsumer`].
+    fn start_stream(
+        &mut self,
+        packet_ids: &[PacketId],
+    ) -> Result<(), StreamError<<T as Write<u8>>::Error>> {
+        if packet_ids.len() > MAX_PACKET_LIST {
+            return Err(StreamError::TooManyPackets);
+        }
+        let mut header = [0; 35];
+        header[0] = 148;
+        header[1] = packet_ids.len() as u8;
+        for (index, id) in packet_ids.iter().enumerate() {
+            header[2 + index] = *id as u8;
+        }
+        self.write(&header[..2 + packet_ids.len()])
+            .map_err(StreamError::Write)
+    }
+
+    /// Pauses the current sensor stream without discarding the packet list (opcode 150).
+    fn pause_stream(&mut self) -> Result<(), <T as Write<u8>>::Error> {
+        self.write(&[150, 0])
+    }
+
+    /// Resumes a previously paused sensor stream (opcode 150).
+    fn resume_stream(&mut self) -> Result<(), <T as Write<u8>>::Error> {
+        self.write(&[150, 1])
+    }
+
+    /// Drives the robot along an arc (opcode 137).
+    ///
+    /// `radius_mm` accepts the spec's sentinels in addition to the `±2000 mm`
+    /// range: `-32768` (`0x8000`) drives straight, `-1` turns in place clockwise
+    /// and `1` turns in place counter-clockwise.
+    fn send_drive(
+        &mut self,
+        velocity_mm_s: i16,
+        radius_mm: i16,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        if !(-500..=500).contains(&velocity_mm_s) {
+            return Err(ActuatorError::OutOfRange);
+        }
+        let radius_ok = matches!(radius_mm, -32768 | -1 | 1) || (-2000..=2000).contains(&radius_mm);
+        if !radius_ok {
+            return Err(ActuatorError::OutOfRange);
+        }
+        let velocity = velocity_mm_s.to_be_bytes();
+        let radius = radius_mm.to_be_bytes();
+        self.write(&[137, velocity[0], velocity[1], radius[0], radius[1]])
+            .map_err(ActuatorError::Write)
+    }
+
+    /// Drives each wheel independently (opcode 145), in `±500 mm/s`.
+    fn send_drive_direct(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        if !(-500..=500).contains(&right) || !(-500..=500).contains(&left) {
+            return Err(ActuatorError::OutOfRange);
+        }
+        let right = right.to_be_bytes();
+        let left = left.to_be_bytes();
+        self.write(&[145, right[0], right[1], left[0], left[1]])
+            .map_err(ActuatorError::Write)
+    }
+
+    /// Drives each wheel by raw PWM (opcode 146), in `±255`.
+    fn send_drive_pwm(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        if !(-255..=255).contains(&right) || !(-255..=255).contains(&left) {
+            return Err(ActuatorError::OutOfRange);
+        }
+        let right = right.to_be_bytes();
+        let left = left.to_be_bytes();
+        self.write(&[146, right[0], right[1], left[0], left[1]])
+            .map_err(ActuatorError::Write)
+    }
+
+    /// Switches the cleaning motors on or off (opcode 138).
+    fn send_motors(
+        &mut self,
+        main: bool,
+        side: bool,
+        vacuum: bool,
+    ) -> Result<(), <T as Write<u8>>::Error> {
+        let bits = (side as u8) | ((vacuum as u8) << 1) | ((main as u8) << 2);
+        self.write(&[138, bits])
+    }
+
+    /// Sets the LED bitfield and the power LED colour/intensity (opcode 139).
+    fn send_leds(
+        &mut self,
+        bits: u8,
+        power_color: u8,
+        power_intensity: u8,
+    ) -> Result<(), <T as Write<u8>>::Error> {
+        self.write(&[139, bits, power_color, power_intensity])
+    }
+
+    /// Sets the four seven-segment digits from ASCII characters (opcode 164).
+    fn send_digit_leds_ascii(&mut self, digits: [u8; 4]) -> Result<(), <T as Write<u8>>::Error> {
+        self.write(&[164, digits[0], digits[1], digits[2], digits[3]])
+    }
+
+    /// Drives the cleaning motors by raw PWM (opcode 144).
+    ///
+    /// The spec caps the main/side duty at `±127` and the vacuum duty at `0..=127`,
+    /// so `-128` and a vacuum above `127` are rejected rather than sent out-of-spec.
+    fn send_motors_pwm(
+        &mut self,
+        main: i8,
+        side: i8,
+        vacuum: u8,
+    ) -> Result<(), ActuatorError<<T as Write<u8>>::Error>> {
+        if main == i8::MIN || side == i8::MIN || vacuum > 127 {
+            return Err(ActuatorError::OutOfRange);
+        }
+        self.write(&[144, main as u8, side as u8, vacuum])
+            .map_err(ActuatorError::Write)
+    }
+
     fn enter_off_state(&mut self) {
         if let Err(_error) = self.write(&[173]) {
             panic!("Error entering the off state failed!");
@@ -258,35 +905,1400 @@ where
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::prelude::U16Ext;
-    use super::*;
-
-    extern crate std;
-
-    use std::assert_eq;
-    use std::cell::RefCell;
-    use std::vec;
-    use std::vec::Vec;
-
-    struct MockSerial<'a> {
-        data: &'a RefCell<Vec<u8>>,
+impl<T, MODE> Rumba<T, MODE>
+where
+    T: SplitSerial,
+{
+    /// Splits the driver into a command ([`RumbaTx`]) and a sensor ([`RumbaRx`]) half.
+    ///
+    /// The typestate `MODE` is preserved on the transmit half, which owns the
+    /// command surface and the `Drop`-sends-Off safety behaviour; the receive
+    /// half owns the sensor-stream decoder. The two can then be moved into
+    /// different execution contexts (for instance an interrupt and the main loop)
+    /// and recombined later with [`Rumba::reunite`].
+    pub fn split(self) -> (RumbaTx<T::Tx, MODE>, RumbaRx<T::Rx>) {
+        let (tx, rx) = self.decompose().split();
+        (
+            RumbaTx {
+                tx: Some(tx),
+                _mode: PhantomData,
+            },
+            RumbaRx::new(rx),
+        )
     }
 
-    impl<'a> Write<u8> for MockSerial<'a> {
-        type Error = core::convert::Infallible;
-        fn write(&mut self, value: u8) -> nb::Result<(), Self::Error> {
-            self.data.borrow_mut().push(value);
-            Ok(())
-        }
-        fn flush(&mut self) -> nb::Result<(), Self::Error> {
-            Ok(())
+    /// Recombines a transmit and receive half into a whole driver.
+    pub fn reunite(tx: RumbaTx<T::Tx, MODE>, rx: RumbaRx<T::Rx>) -> Self {
+        let port = T::reunite(tx.decompose(), rx.rx);
+        Rumba {
+            io_port: Some(port),
+            _mode: PhantomData,
         }
     }
+}
 
-    impl<'a> Read<u8> for MockSerial<'a> {
-        type Error = core::convert::Infallible;
+/// Transmit half of a split [`Rumba`], owning the command channel.
+///
+/// It carries the typestate `MODE` so the command surface stays mode-gated, and
+/// it owns the `Drop`-sends-Off safety behaviour because it is the half able to
+/// write to the port.
+pub struct RumbaTx<TX: Write<u8>, MODE> {
+    tx: Option<TX>,
+    _mode: PhantomData<MODE>,
+}
+
+/// Receive half of a split [`Rumba`], owning the sensor-stream decoder.
+pub struct RumbaRx<RX: Read<u8>> {
+    rx: RX,
+    decoder: stream::FrameDecoder,
+}
+
+impl<RX> RumbaRx<RX>
+where
+    RX: Read<u8>,
+{
+    fn new(rx: RX) -> Self {
+        RumbaRx {
+            rx,
+            decoder: stream::FrameDecoder::new(),
+        }
+    }
+
+    /// Drains the currently available bytes, decoding at most one complete frame.
+    ///
+    /// Decoded packets are written into `out` in wire order and their count is
+    /// returned; `Ok(None)` means no complete, valid frame was available yet.
+    /// Intended to be called from the context that owns the RX interrupt.
+    pub fn poll(&mut self, out: &mut [SensorData]) -> Result<Option<usize>, <RX as Read<u8>>::Error> {
+        loop {
+            match self.rx.read() {
+                Ok(byte) => {
+                    if let Some(count) = self.decoder.push(byte, out) {
+                        return Ok(Some(count));
+                    }
+                }
+                Err(nb::Error::WouldBlock) => return Ok(None),
+                Err(nb::Error::Other(error)) => return Err(error),
+            }
+        }
+    }
+}
+
+impl<TX, MODE> RumbaTx<TX, MODE>
+where
+    TX: Write<u8>,
+{
+    fn write(&mut self, buffer: &[u8]) -> Result<(), <TX as Write<u8>>::Error> {
+        for element in buffer {
+            nb::block!(self.tx.as_mut().unwrap().write(*element))?;
+        }
+        Ok(())
+    }
+
+    /// Subscribes to a background stream of sensor packets (opcode 148).
+    fn start_stream(
+        &mut self,
+        packet_ids: &[PacketId],
+    ) -> Result<(), StreamError<<TX as Write<u8>>::Error>> {
+        if packet_ids.len() > MAX_PACKET_LIST {
+            return Err(StreamError::TooManyPackets);
+        }
+        let mut header = [0; 35];
+        header[0] = 148;
+        header[1] = packet_ids.len() as u8;
+        for (index, id) in packet_ids.iter().enumerate() {
+            header[2 + index] = *id as u8;
+        }
+        self.write(&header[..2 + packet_ids.len()])
+            .map_err(StreamError::Write)
+    }
+
+    /// Drives the robot along an arc (opcode 137).
+    ///
+    /// `radius_mm` accepts the spec's sentinels in addition to the `±2000 mm`
+    /// range: `-32768` (`0x8000`) drives straight, `-1` turns in place clockwise
+    /// and `1` turns in place counter-clockwise.
+    fn send_drive(
+        &mut self,
+        velocity_mm_s: i16,
+        radius_mm: i16,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        if !(-500..=500).contains(&velocity_mm_s) {
+            return Err(ActuatorError::OutOfRange);
+        }
+        let radius_ok = matches!(radius_mm, -32768 | -1 | 1) || (-2000..=2000).contains(&radius_mm);
+        if !radius_ok {
+            return Err(ActuatorError::OutOfRange);
+        }
+        let velocity = velocity_mm_s.to_be_bytes();
+        let radius = radius_mm.to_be_bytes();
+        self.write(&[137, velocity[0], velocity[1], radius[0], radius[1]])
+            .map_err(ActuatorError::Write)
+    }
+
+    /// Drives each wheel independently (opcode 145), in `±500 mm/s`.
+    fn send_drive_direct(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        if !(-500..=500).contains(&right) || !(-500..=500).contains(&left) {
+            return Err(ActuatorError::OutOfRange);
+        }
+        let right = right.to_be_bytes();
+        let left = left.to_be_bytes();
+        self.write(&[145, right[0], right[1], left[0], left[1]])
+            .map_err(ActuatorError::Write)
+    }
+
+    /// Drives each wheel by raw PWM (opcode 146), in `±255`.
+    fn send_drive_pwm(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        if !(-255..=255).contains(&right) || !(-255..=255).contains(&left) {
+            return Err(ActuatorError::OutOfRange);
+        }
+        let right = right.to_be_bytes();
+        let left = left.to_be_bytes();
+        self.write(&[146, right[0], right[1], left[0], left[1]])
+            .map_err(ActuatorError::Write)
+    }
+
+    /// Switches the cleaning motors on or off (opcode 138).
+    fn send_motors(
+        &mut self,
+        main: bool,
+        side: bool,
+        vacuum: bool,
+    ) -> Result<(), <TX as Write<u8>>::Error> {
+        let bits = (side as u8) | ((vacuum as u8) << 1) | ((main as u8) << 2);
+        self.write(&[138, bits])
+    }
+
+    /// Sets the LED bitfield and the power LED colour/intensity (opcode 139).
+    fn send_leds(
+        &mut self,
+        bits: u8,
+        power_color: u8,
+        power_intensity: u8,
+    ) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[139, bits, power_color, power_intensity])
+    }
+
+    /// Sets the four seven-segment digits from ASCII characters (opcode 164).
+    fn send_digit_leds_ascii(&mut self, digits: [u8; 4]) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[164, digits[0], digits[1], digits[2], digits[3]])
+    }
+
+    /// Drives the cleaning motors by raw PWM (opcode 144).
+    ///
+    /// The spec caps the main/side duty at `±127` and the vacuum duty at `0..=127`,
+    /// so `-128` and a vacuum above `127` are rejected rather than sent out-of-spec.
+    fn send_motors_pwm(
+        &mut self,
+        main: i8,
+        side: i8,
+        vacuum: u8,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        if main == i8::MIN || side == i8::MIN || vacuum > 127 {
+            return Err(ActuatorError::OutOfRange);
+        }
+        self.write(&[144, main as u8, side as u8, vacuum])
+            .map_err(ActuatorError::Write)
+    }
+
+    fn enter_off_state(&mut self) {
+        if let Err(_error) = self.write(&[173]) {
+            panic!("Error entering the off state failed!");
+        }
+    }
+
+    fn enter_passive_state(&mut self) {
+        if let Err(_error) = self.write(&[128]) {
+            panic!("Error entering the passive state failed!");
+        }
+    }
+
+    fn decompose(self) -> TX {
+        let mut tx = core::mem::ManuallyDrop::new(self);
+        tx.tx.take().unwrap()
+    }
+
+    fn transition<NEWMODE>(mut self) -> RumbaTx<TX, NEWMODE> {
+        RumbaTx {
+            tx: Some(self.decompose()),
+            _mode: PhantomData,
+        }
+    }
+}
+
+impl<TX> RumbaTx<TX, mode::Passive>
+where
+    TX: Write<u8>,
+{
+    /// Switches to the Off state
+    pub fn into_off(mut self) -> RumbaTx<TX, mode::Off> {
+        self.enter_off_state();
+        self.transition()
+    }
+
+    /// Switches to the Safe state
+    pub fn into_safe(mut self) -> RumbaTx<TX, mode::Safe> {
+        if let Err(_error) = self.write(&[131]) {
+            panic!("Error entering the safe state failed!");
+        }
+        self.transition()
+    }
+
+    /// Sends a predefined song to the Rumba at the specified slot
+    pub fn send_song(
+        &mut self,
+        song: SongSlot,
+        notes: &[Note],
+    ) -> Result<(), SongError<<TX as Write<u8>>::Error>> {
+        if notes.len() > MAX_SONG_NOTES {
+            return Err(SongError::TooManyNotes);
+        }
+        let mut buffer = [0; 35];
+        buffer[0] = 140;
+        buffer[1] = song as u8;
+        buffer[2] = notes.len() as u8;
+        for (index, element) in notes.iter().enumerate() {
+            buffer[3 + index * 2] = element.midi_value();
+            buffer[4 + index * 2] = element.duration();
+        }
+        self.write(&buffer[..3 + 2 * notes.len()])
+            .map_err(SongError::Write)
+    }
+
+    /// Starts/stops the cleaning mode in Rumba
+    pub fn clean(&mut self) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[135])
+    }
+
+    /// Starts cleaning in max mode
+    pub fn max_clean(&mut self) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[136])
+    }
+
+    /// Subscribes to a background sensor stream (opcode 148)
+    pub fn stream(
+        &mut self,
+        packet_ids: &[PacketId],
+    ) -> Result<(), StreamError<<TX as Write<u8>>::Error>> {
+        self.start_stream(packet_ids)
+    }
+
+    /// Pauses the background sensor stream (opcode 150)
+    pub fn pause(&mut self) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[150, 0])
+    }
+
+    /// Resumes the background sensor stream (opcode 150)
+    pub fn resume(&mut self) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[150, 1])
+    }
+}
+
+impl<TX> RumbaTx<TX, mode::Safe>
+where
+    TX: Write<u8>,
+{
+    /// Plays the specified song
+    pub fn play_song(&mut self, song: SongSlot) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[141, song as u8])
+    }
+
+    /// Drives the robot along an arc (opcode 137)
+    pub fn drive(
+        &mut self,
+        velocity_mm_s: i16,
+        radius_mm: i16,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        self.send_drive(velocity_mm_s, radius_mm)
+    }
+
+    /// Drives each wheel independently (opcode 145)
+    pub fn drive_direct(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        self.send_drive_direct(right, left)
+    }
+
+    /// Drives each wheel by raw PWM (opcode 146)
+    pub fn drive_pwm(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        self.send_drive_pwm(right, left)
+    }
+
+    /// Switches the cleaning motors on or off (opcode 138)
+    pub fn motors(
+        &mut self,
+        main: bool,
+        side: bool,
+        vacuum: bool,
+    ) -> Result<(), <TX as Write<u8>>::Error> {
+        self.send_motors(main, side, vacuum)
+    }
+
+    /// Sets the LED bitfield and power LED colour/intensity (opcode 139)
+    pub fn leds(
+        &mut self,
+        bits: u8,
+        power_color: u8,
+        power_intensity: u8,
+    ) -> Result<(), <TX as Write<u8>>::Error> {
+        self.send_leds(bits, power_color, power_intensity)
+    }
+
+    /// Sets the four seven-segment digits from ASCII characters (opcode 164)
+    pub fn digit_leds_ascii(&mut self, digits: [u8; 4]) -> Result<(), <TX as Write<u8>>::Error> {
+        self.send_digit_leds_ascii(digits)
+    }
+
+    /// Subscribes to a background sensor stream (opcode 148)
+    pub fn stream(
+        &mut self,
+        packet_ids: &[PacketId],
+    ) -> Result<(), StreamError<<TX as Write<u8>>::Error>> {
+        self.start_stream(packet_ids)
+    }
+
+    /// Pauses the background sensor stream (opcode 150)
+    pub fn pause(&mut self) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[150, 0])
+    }
+
+    /// Resumes the background sensor stream (opcode 150)
+    pub fn resume(&mut self) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[150, 1])
+    }
+
+    /// Switches to the Full state
+    pub fn into_full(mut self) -> RumbaTx<TX, mode::Full> {
+        if let Err(_error) = self.write(&[132]) {
+            panic!("Error entering the full state failed!");
+        }
+        self.transition()
+    }
+
+    /// Switches to the Off state
+    pub fn into_off(mut self) -> RumbaTx<TX, mode::Off> {
+        self.enter_off_state();
+        self.transition()
+    }
+
+    /// Switches to the Passive state
+    pub fn into_passive(mut self) -> RumbaTx<TX, mode::Passive> {
+        self.enter_passive_state();
+        self.transition()
+    }
+}
+
+impl<TX> RumbaTx<TX, mode::Full>
+where
+    TX: Write<u8>,
+{
+    /// Plays the specified song
+    pub fn play_song(&mut self, song: SongSlot) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[141, song as u8])
+    }
+
+    /// Drives the robot along an arc (opcode 137)
+    pub fn drive(
+        &mut self,
+        velocity_mm_s: i16,
+        radius_mm: i16,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        self.send_drive(velocity_mm_s, radius_mm)
+    }
+
+    /// Drives each wheel independently (opcode 145)
+    pub fn drive_direct(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        self.send_drive_direct(right, left)
+    }
+
+    /// Drives each wheel by raw PWM (opcode 146)
+    pub fn drive_pwm(
+        &mut self,
+        right: i16,
+        left: i16,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        self.send_drive_pwm(right, left)
+    }
+
+    /// Switches the cleaning motors on or off (opcode 138)
+    pub fn motors(
+        &mut self,
+        main: bool,
+        side: bool,
+        vacuum: bool,
+    ) -> Result<(), <TX as Write<u8>>::Error> {
+        self.send_motors(main, side, vacuum)
+    }
+
+    /// Sets the LED bitfield and power LED colour/intensity (opcode 139)
+    pub fn leds(
+        &mut self,
+        bits: u8,
+        power_color: u8,
+        power_intensity: u8,
+    ) -> Result<(), <TX as Write<u8>>::Error> {
+        self.send_leds(bits, power_color, power_intensity)
+    }
+
+    /// Sets the four seven-segment digits from ASCII characters (opcode 164)
+    pub fn digit_leds_ascii(&mut self, digits: [u8; 4]) -> Result<(), <TX as Write<u8>>::Error> {
+        self.send_digit_leds_ascii(digits)
+    }
+
+    /// Drives the cleaning motors by raw PWM (opcode 144).
+    ///
+    /// Unlike [`motors`](Self::motors), which only toggles them, this exposes the
+    /// low-level PWM duty cycles. It is offered only in Full mode, the mode that
+    /// grants complete, unsupervised control over the actuators.
+    pub fn motors_pwm(
+        &mut self,
+        main: i8,
+        side: i8,
+        vacuum: u8,
+    ) -> Result<(), ActuatorError<<TX as Write<u8>>::Error>> {
+        self.send_motors_pwm(main, side, vacuum)
+    }
+
+    /// Subscribes to a background sensor stream (opcode 148)
+    pub fn stream(
+        &mut self,
+        packet_ids: &[PacketId],
+    ) -> Result<(), StreamError<<TX as Write<u8>>::Error>> {
+        self.start_stream(packet_ids)
+    }
+
+    /// Pauses the background sensor stream (opcode 150)
+    pub fn pause(&mut self) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[150, 0])
+    }
+
+    /// Resumes the background sensor stream (opcode 150)
+    pub fn resume(&mut self) -> Result<(), <TX as Write<u8>>::Error> {
+        self.write(&[150, 1])
+    }
+
+    /// Switches to the Safe state
+    pub fn into_safe(mut self) -> RumbaTx<TX, mode::Safe> {
+        if let Err(_error) = self.write(&[131]) {
+            panic!("Error entering the safe state failed!");
+        }
+        self.transition()
+    }
+
+    /// Switches to the Passive state
+    pub fn into_passive(mut self) -> RumbaTx<TX, mode::Passive> {
+        self.enter_passive_state();
+        self.transition()
+    }
+
+    /// Switches to the Off state
+    pub fn into_off(mut self) -> RumbaTx<TX, mode::Off> {
+        self.enter_off_state();
+        self.transition()
+    }
+}
+
+impl<TX, MODE> Drop for RumbaTx<TX, MODE>
+where
+    TX: Write<u8>,
+{
+    fn drop(&mut self) {
+        self.enter_off_state();
+    }
+}
+
+/// Asynchronous variant of the driver built on top of `embedded-io-async`.
+///
+/// The blocking [`Rumba`] spins on [`nb::block!`] for every byte and gives the
+/// caller no way to yield, so Rumba commands cannot be interleaved with other
+/// work on a cooperative executor such as the one described in the embassy docs.
+/// This module offers the mode transitions, song upload and playback, cleaning
+/// and sensor-stream commands of the blocking API on top of the `embedded-io-async`
+/// byte I/O traits; sensor queries, actuator commands and Full mode are not
+/// provided here. A [`Timer`] bound lets the settle delay after each mode-change
+/// opcode be `.await`ed instead of busy-waited; all other commands are written
+/// without delays between their bytes.
+pub mod asynchronous {
+    use super::{
+        mode, stream::FrameDecoder, Note, PacketId, SensorData, SongError, SongSlot, StreamError,
+        MAX_PACKET_LIST, MAX_SONG_NOTES,
+    };
+    use core::marker::PhantomData;
+    use embedded_io_async::{Read, Write};
+
+    /// Abstraction over an async delay source used to honour the OI timing
+    /// requirements without blocking the executor.
+    ///
+    /// The OI spec asks for a settle time after a mode change before the next
+    /// command is accepted. Implementors typically forward to their executor's
+    /// timer (for instance `embassy_time::Timer`).
+    #[allow(async_fn_in_trait)]
+    pub trait Timer {
+        /// Completes after at least `ms` milliseconds have elapsed.
+        async fn after_ms(&mut self, ms: u32);
+    }
+
+    /// Settle time requested by the OI spec after a mode-change opcode.
+    const MODE_SETTLE_MS: u32 = 20;
+
+    /// Representation of a Roomba instance driven asynchronously.
+    pub struct Rumba<T, D, MODE>
+    where
+        T: Read + Write,
+        D: Timer,
+    {
+        io_port: Option<T>,
+        timer: D,
+        _mode: PhantomData<MODE>,
+    }
+
+    impl<T, D> Rumba<T, D, mode::Off>
+    where
+        T: Read + Write,
+        D: Timer,
+    {
+        /// Constructs a roomba from the given serial port and timer in the Off state
+        pub fn new(io_port: T, timer: D) -> Self {
+            Rumba {
+                io_port: Some(io_port),
+                timer,
+                _mode: PhantomData,
+            }
+        }
+
+        /// Switches to the Passive state
+        pub async fn into_passive(mut self) -> Result<Rumba<T, D, mode::Passive>, T::Error> {
+            self.enter_passive_state().await?;
+            Ok(self.transition())
+        }
+    }
+
+    impl<T, D> Rumba<T, D, mode::Passive>
+    where
+        T: Read + Write,
+        D: Timer,
+    {
+        /// Switches to the Off state
+        pub async fn into_off(mut self) -> Result<Rumba<T, D, mode::Off>, T::Error> {
+            self.enter_off_state().await?;
+            Ok(self.transition())
+        }
+
+        /// Switches to the Safe state
+        pub async fn into_safe(mut self) -> Result<Rumba<T, D, mode::Safe>, T::Error> {
+            self.write(&[131]).await?;
+            self.timer.after_ms(MODE_SETTLE_MS).await;
+            Ok(self.transition())
+        }
+
+        /// Sends a predefined song to the Rumba at the specified slot
+        pub async fn send_song(
+            &mut self,
+            song: SongSlot,
+            notes: &[Note],
+        ) -> Result<(), SongError<T::Error>> {
+            if notes.len() > MAX_SONG_NOTES {
+                return Err(SongError::TooManyNotes);
+            }
+            let mut buffer = [0; 35];
+            buffer[0] = 140;
+            buffer[1] = song as u8;
+            buffer[2] = notes.len() as u8;
+            for (index, element) in notes.iter().enumerate() {
+                buffer[3 + index * 2] = element.midi_value();
+                buffer[4 + index * 2] = element.duration();
+            }
+            self.write(&buffer[..3 + 2 * notes.len()])
+                .await
+                .map_err(SongError::Write)
+        }
+
+        /// Starts/stops the cleaning mode in Rumba
+        pub async fn clean(&mut self) -> Result<(), T::Error> {
+            self.write(&[135]).await
+        }
+
+        /// Starts cleaning in max mode
+        pub async fn max_clean(&mut self) -> Result<(), T::Error> {
+            self.write(&[136]).await
+        }
+
+        /// Subscribes to a background sensor stream (opcode 148)
+        pub async fn stream(
+            &mut self,
+            packet_ids: &[PacketId],
+        ) -> Result<(), StreamError<T::Error>> {
+            self.start_stream(packet_ids).await
+        }
+
+        /// Pauses the background sensor stream (opcode 150)
+        pub async fn pause(&mut self) -> Result<(), T::Error> {
+            self.write(&[150, 0]).await
+        }
+
+        /// Resumes the background sensor stream (opcode 150)
+        pub async fn resume(&mut self) -> Result<(), T::Error> {
+            self.write(&[150, 1]).await
+        }
+
+        /// Awaits and decodes the next complete sensor-stream frame
+        pub async fn read_stream(
+            &mut self,
+            out: &mut [SensorData],
+        ) -> Result<Option<usize>, T::Error> {
+            self.read_frame(out).await
+        }
+    }
+
+    impl<T, D> Rumba<T, D, mode::Safe>
+    where
+        T: Read + Write,
+        D: Timer,
+    {
+        /// Plays the specified song
+        pub async fn play_song(&mut self, song: SongSlot) -> Result<(), T::Error> {
+            self.write(&[141, song as u8]).await
+        }
+
+        /// Subscribes to a background sensor stream (opcode 148)
+        pub async fn stream(
+            &mut self,
+            packet_ids: &[PacketId],
+        ) -> Result<(), StreamError<T::Error>> {
+            self.start_stream(packet_ids).await
+        }
+
+        /// Pauses the background sensor stream (opcode 150)
+        pub async fn pause(&mut self) -> Result<(), T::Error> {
+            self.write(&[150, 0]).await
+        }
+
+        /// Resumes the background sensor stream (opcode 150)
+        pub async fn resume(&mut self) -> Result<(), T::Error> {
+            self.write(&[150, 1]).await
+        }
+
+        /// Awaits and decodes the next complete sensor-stream frame
+        pub async fn read_stream(
+            &mut self,
+            out: &mut [SensorData],
+        ) -> Result<Option<usize>, T::Error> {
+            self.read_frame(out).await
+        }
+
+        /// Switches to the Off state
+        pub async fn into_off(mut self) -> Result<Rumba<T, D, mode::Off>, T::Error> {
+            self.enter_off_state().await?;
+            Ok(self.transition())
+        }
+
+        /// Switches to the Passive state
+        pub async fn into_passive(mut self) -> Result<Rumba<T, D, mode::Passive>, T::Error> {
+            self.enter_passive_state().await?;
+            Ok(self.transition())
+        }
+    }
+
+    impl<T, D, MODE> Rumba<T, D, MODE>
+    where
+        T: Read + Write,
+        D: Timer,
+    {
+        async fn write(&mut self, buffer: &[u8]) -> Result<(), T::Error> {
+            self.io_port.as_mut().unwrap().write_all(buffer).await
+        }
+
+        async fn enter_off_state(&mut self) -> Result<(), T::Error> {
+            self.write(&[173]).await?;
+            self.timer.after_ms(MODE_SETTLE_MS).await;
+            Ok(())
+        }
+
+        async fn enter_passive_state(&mut self) -> Result<(), T::Error> {
+            self.write(&[128]).await?;
+            self.timer.after_ms(MODE_SETTLE_MS).await;
+            Ok(())
+        }
+
+        /// Subscribes to a background sensor stream (opcode 148).
+        async fn start_stream(
+            &mut self,
+            packet_ids: &[PacketId],
+        ) -> Result<(), StreamError<T::Error>> {
+            if packet_ids.len() > MAX_PACKET_LIST {
+                return Err(StreamError::TooManyPackets);
+            }
+            let mut header = [0; 35];
+            header[0] = 148;
+            header[1] = packet_ids.len() as u8;
+            for (index, id) in packet_ids.iter().enumerate() {
+                header[2 + index] = *id as u8;
+            }
+            self.write(&header[..2 + packet_ids.len()])
+                .await
+                .map_err(StreamError::Write)
+        }
+
+        /// Awaits and decodes the next complete sensor-stream frame.
+        ///
+        /// Bytes are pulled from the port as they arrive and reassembled with a
+        /// [`FrameDecoder`]; the call resolves with `Some(count)` once a whole,
+        /// checksum-valid frame has been received (its packets written into `out`
+        /// in wire order), or `None` if the port reaches end-of-stream first. This
+        /// is the awaitable counterpart to the polled
+        /// [`StreamConsumer`](super::stream::StreamConsumer).
+        async fn read_frame(&mut self, out: &mut [SensorData]) -> Result<Option<usize>, T::Error> {
+            let mut decoder = FrameDecoder::new();
+            let mut byte = [0u8; 1];
+            loop {
+                if self.io_port.as_mut().unwrap().read(&mut byte).await? == 0 {
+                    // End of stream: no more bytes will arrive.
+                    return Ok(None);
+                }
+                if let Some(count) = decoder.push(byte[0], out) {
+                    return Ok(Some(count));
+                }
+            }
+        }
+
+        /// Rebuilds the driver in a new mode, carrying the port and timer over.
+        ///
+        /// A dedicated helper is needed because the async path cannot rely on a
+        /// `Drop` implementation (dropping cannot `.await`), so every transition
+        /// moves the owned resources explicitly.
+        fn transition<NEWMODE>(mut self) -> Rumba<T, D, NEWMODE> {
+            Rumba {
+                io_port: self.io_port.take(),
+                timer: self.timer,
+                _mode: PhantomData,
+            }
+        }
+    }
+}
+
+/// Background decoding of the OI sensor stream (opcode 148).
+///
+/// The robot pushes a sensor frame every 15 ms. Decoding it in the RX interrupt
+/// would be too heavy, so the raw bytes are handed to a [`StreamReader`] from the
+/// interrupt and pulled back out by a [`StreamConsumer`] in the main context. The
+/// two halves are decoupled by a lock-free single-producer/single-consumer
+/// [`RingBuffer`] that lives in a `static`.
+pub mod stream {
+    use super::{PacketId, SensorData};
+    use core::ptr;
+    use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+    /// Largest frame the consumer will reassemble (`19`, count, payload, checksum).
+    const MAX_FRAME: usize = 64;
+
+    /// Lock-free single-producer/single-consumer byte ring buffer.
+    ///
+    /// The backing storage is attached at runtime with [`init`](RingBuffer::init)
+    /// so the buffer can live in a `static` and be shared between an interrupt
+    /// (the single writer) and the main context (the single reader) running at
+    /// different priorities. A slot is kept free to distinguish the empty and
+    /// full states, following the `end + 1 == start` convention.
+    pub struct RingBuffer {
+        buffer: AtomicPtr<u8>,
+        start: AtomicUsize,
+        end: AtomicUsize,
+        len: AtomicUsize,
+    }
+
+    impl RingBuffer {
+        /// Creates a detached ring buffer with no backing storage.
+        pub const fn new() -> Self {
+            RingBuffer {
+                buffer: AtomicPtr::new(ptr::null_mut()),
+                start: AtomicUsize::new(0),
+                end: AtomicUsize::new(0),
+                len: AtomicUsize::new(0),
+            }
+        }
+
+        /// Attaches `buf` (of `len` bytes) as the backing storage and resets the indices.
+        ///
+        /// # Safety
+        /// `buf` must point to at least `len` valid, writable bytes that outlive
+        /// every use of this ring buffer, and no reader or writer may be active
+        /// while `init` runs.
+        pub unsafe fn init(&self, buf: *mut u8, len: usize) {
+            self.start.store(0, Ordering::Relaxed);
+            self.end.store(0, Ordering::Relaxed);
+            self.len.store(len, Ordering::Relaxed);
+            self.buffer.store(buf, Ordering::Release);
+        }
+
+        /// Detaches the backing storage.
+        ///
+        /// # Safety
+        /// No reader or writer may be active while `deinit` runs.
+        pub unsafe fn deinit(&self) {
+            self.buffer.store(ptr::null_mut(), Ordering::Release);
+            self.len.store(0, Ordering::Relaxed);
+        }
+
+        /// Returns `true` when there are no bytes to read.
+        pub fn is_empty(&self) -> bool {
+            self.start.load(Ordering::Acquire) == self.end.load(Ordering::Acquire)
+        }
+
+        /// Returns `true` when the next write would overrun the reader.
+        pub fn is_full(&self) -> bool {
+            let len = self.len.load(Ordering::Relaxed);
+            if len == 0 {
+                return true;
+            }
+            let end = self.end.load(Ordering::Acquire);
+            let start = self.start.load(Ordering::Acquire);
+            (end + 1) % len == start
+        }
+
+        /// Pushes a byte from the producer side. Returns `false` if the buffer is full.
+        pub fn push(&self, byte: u8) -> bool {
+            let len = self.len.load(Ordering::Relaxed);
+            let buffer = self.buffer.load(Ordering::Acquire);
+            if buffer.is_null() || len == 0 {
+                return false;
+            }
+            let end = self.end.load(Ordering::Relaxed);
+            let next = (end + 1) % len;
+            if next == self.start.load(Ordering::Acquire) {
+                return false;
+            }
+            // Safety: `end < len` and the slot is owned by the producer until the
+            // store to `end` publishes it to the consumer.
+            unsafe { ptr::write(buffer.add(end), byte) };
+            self.end.store(next, Ordering::Release);
+            true
+        }
+
+        /// Pops a byte from the consumer side. Returns `None` if the buffer is empty.
+        pub fn pop(&self) -> Option<u8> {
+            let len = self.len.load(Ordering::Relaxed);
+            let buffer = self.buffer.load(Ordering::Acquire);
+            if buffer.is_null() || len == 0 {
+                return None;
+            }
+            let start = self.start.load(Ordering::Relaxed);
+            if start == self.end.load(Ordering::Acquire) {
+                return None;
+            }
+            // Safety: `start < len` and the slot was published by the producer.
+            let byte = unsafe { ptr::read(buffer.add(start)) };
+            self.start.store((start + 1) % len, Ordering::Release);
+            Some(byte)
+        }
+    }
+
+    impl Default for RingBuffer {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Producer half: fed raw bytes from the UART RX interrupt.
+    pub struct StreamReader {
+        ring: &'static RingBuffer,
+    }
+
+    impl StreamReader {
+        /// Builds a reader that writes into the shared ring buffer.
+        pub fn new(ring: &'static RingBuffer) -> Self {
+            StreamReader { ring }
+        }
+
+        /// Pushes a freshly received byte. Returns `false` if the buffer overflowed.
+        pub fn push(&mut self, byte: u8) -> bool {
+            self.ring.push(byte)
+        }
+    }
+
+    /// Byte-oriented reassembler for OI sensor frames.
+    ///
+    /// Fed one received byte at a time regardless of where the bytes come from
+    /// (a [`RingBuffer`] or a raw [`Read`](super::Read) port), it resynchronises
+    /// on the header, tracks the declared length and, once a whole frame is in,
+    /// validates its checksum and decodes the contained packets.
+    pub struct FrameDecoder {
+        frame: [u8; MAX_FRAME],
+        index: usize,
+    }
+
+    impl FrameDecoder {
+        /// Builds an empty decoder waiting for the next frame header.
+        pub const fn new() -> Self {
+            FrameDecoder {
+                frame: [0; MAX_FRAME],
+                index: 0,
+            }
+        }
+
+        /// Feeds a single byte. Returns the number of decoded packets written to
+        /// `out` when `byte` completes a valid frame, and `None` otherwise.
+        /// Frames whose checksum fails are dropped silently.
+        pub fn push(&mut self, byte: u8, out: &mut [SensorData]) -> Option<usize> {
+            // Resynchronise on the frame header (19) if we are at the start.
+            if self.index == 0 && byte != 19 {
+                return None;
+            }
+            if self.index < MAX_FRAME {
+                self.frame[self.index] = byte;
+            }
+            self.index += 1;
+
+            // Once the count byte is in we know the total frame length:
+            // header + count + payload + checksum.
+            if self.index < 2 {
+                return None;
+            }
+            let payload = self.frame[1] as usize;
+            let total = payload + 3;
+            if total > MAX_FRAME {
+                // Malformed length; drop and resynchronise.
+                self.index = 0;
+                return None;
+            }
+            if self.index != total {
+                return None;
+            }
+            let result = self.decode_frame(payload, out);
+            self.index = 0;
+            result
+        }
+
+        /// Validates the checksum of a completed frame and decodes its packets.
+        fn decode_frame(&self, payload: usize, out: &mut [SensorData]) -> Option<usize> {
+            let total = payload + 3;
+            let sum = self.frame[..total]
+                .iter()
+                .fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+            if sum != 0 {
+                return None;
+            }
+
+            let mut count = 0;
+            let mut cursor = 2;
+            let end = 2 + payload;
+            while cursor < end {
+                let id = PacketId::from_id(self.frame[cursor])?;
+                cursor += 1;
+                if cursor + id.byte_len() > end {
+                    return None;
+                }
+                if count < out.len() {
+                    out[count] = id.decode(&self.frame[cursor..cursor + id.byte_len()]);
+                    count += 1;
+                }
+                cursor += id.byte_len();
+            }
+            Some(count)
+        }
+    }
+
+    impl Default for FrameDecoder {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    /// Consumer half: polled from the main context to reassemble sensor frames.
+    pub struct StreamConsumer {
+        ring: &'static RingBuffer,
+        decoder: FrameDecoder,
+    }
+
+    impl StreamConsumer {
+        /// Builds a consumer that reads from the shared ring buffer.
+        pub fn new(ring: &'static RingBuffer) -> Self {
+            StreamConsumer {
+                ring,
+                decoder: FrameDecoder::new(),
+            }
+        }
+
+        /// Drains the ring buffer, decoding at most one complete frame per call.
+        ///
+        /// Decoded packets are written into `out` in wire order and the number of
+        /// packets is returned. `None` means no complete, valid frame was
+        /// available yet. Frames whose checksum fails are dropped silently.
+        pub fn poll(&mut self, out: &mut [SensorData]) -> Option<usize> {
+            while let Some(byte) = self.ring.pop() {
+                if let Some(count) = self.decoder.push(byte, out) {
+                    return Some(count);
+                }
+            }
+            None
+        }
+    }
+}
+
+/// A host-side Roomba Open Interface simulator.
+///
+/// Following the emulator-hal idea of modelling a device behind the very HAL
+/// traits the driver speaks, [`RumbaSimulator`] implements
+/// [`Read`](embedded_hal::serial::Read) + [`Write`](embedded_hal::serial::Write)
+/// and runs a small OI state machine: it tracks the current mode, rejects
+/// opcodes that are illegal for that mode (for instance a drive command while
+/// Passive), stores the four song slots, models a battery that drains as time
+/// advances and answers opcode 142/149 queries and 148 streams with coherent
+/// synthetic sensor data. This lets users drive the real [`Rumba`] against a
+/// simulated robot entirely on the host.
+pub mod simulator {
+    use super::PacketId;
+    use embedded_hal::serial::{Read, Write};
+
+    /// Mode the simulated robot is currently in.
+    #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+    enum SimMode {
+        Off,
+        Passive,
+        Safe,
+        Full,
+    }
+
+    /// Capacity of the synthetic response FIFO, in bytes.
+    const OUT_LEN: usize = 128;
+    /// Maximum number of packets a single stream subscription may carry.
+    const STREAM_LEN: usize = 16;
+
+    /// Simulated Roomba speaking the Open Interface over the HAL serial traits.
+    pub struct RumbaSimulator {
+        mode: SimMode,
+        songs: [[u8; 32]; 4],
+        song_len: [u8; 4],
+        battery_mah: u16,
+        voltage_mv: u16,
+        distance_mm: i16,
+        angle_deg: i16,
+
+        opcode: Option<u8>,
+        args: [u8; 35],
+        args_len: usize,
+
+        stream_ids: [u8; STREAM_LEN],
+        stream_count: usize,
+        stream_paused: bool,
+
+        out: [u8; OUT_LEN],
+        head: usize,
+        tail: usize,
+    }
+
+    impl RumbaSimulator {
+        /// Builds a simulator in the Off state with a freshly charged battery.
+        pub fn new() -> Self {
+            RumbaSimulator {
+                mode: SimMode::Off,
+                songs: [[0; 32]; 4],
+                song_len: [0; 4],
+                battery_mah: 2600,
+                voltage_mv: 16000,
+                distance_mm: 0,
+                angle_deg: 0,
+                opcode: None,
+                args: [0; 35],
+                args_len: 0,
+                stream_ids: [0; STREAM_LEN],
+                stream_count: 0,
+                stream_paused: false,
+                out: [0; OUT_LEN],
+                head: 0,
+                tail: 0,
+            }
+        }
+
+        /// Advances the simulated time by `ms` milliseconds.
+        ///
+        /// While the robot is out of Off the battery drains by 1 mAh for every
+        /// whole second in `ms`, so calls shorter than one second do not drain it.
+        /// Distance and angle are not modelled and always read 0. If a stream is
+        /// subscribed and not paused, one frame is emitted per whole 15 ms slice
+        /// of `ms`; a frame that does not fit in the FIFO is not emitted, so the
+        /// FIFO never overruns.
+        pub fn advance_time(&mut self, ms: u32) {
+            if self.mode != SimMode::Off {
+                let drain = (ms / 1000) as u16;
+                self.battery_mah = self.battery_mah.saturating_sub(drain);
+                self.voltage_mv = self.voltage_mv.saturating_sub(drain / 4);
+            }
+            if self.stream_count > 0 && !self.stream_paused {
+                let frames = ms / 15;
+                for _ in 0..frames {
+                    self.emit_stream_frame();
+                }
+            }
+        }
+
+        /// Emits a single stream frame for the current subscription.
+        fn emit_stream_frame(&mut self) {
+            let mut frame = [0u8; 3 + STREAM_LEN * 3];
+            let mut len = 2; // reserve header + count
+            for index in 0..self.stream_count {
+                let id = self.stream_ids[index];
+                if let Some(packet) = PacketId::from_id(id) {
+                    frame[len] = id;
+                    len += 1;
+                    len += self.encode_packet(packet, &mut frame[len..]);
+                }
+            }
+            let payload = len - 2;
+            frame[0] = 19;
+            frame[1] = payload as u8;
+            let sum = frame[..len]
+                .iter()
+                .fold(0u8, |acc, byte| acc.wrapping_add(*byte));
+            frame[len] = sum.wrapping_neg();
+            len += 1;
+            let free = (self.head + OUT_LEN - self.tail - 1) % OUT_LEN;
+            if len > free {
+                return;
+            }
+            self.push_out(&frame[..len]);
+        }
+
+        /// Encodes the current value of `packet` into `buf`, returning its length.
+        fn encode_packet(&mut self, packet: PacketId, buf: &mut [u8]) -> usize {
+            match packet {
+                PacketId::BumpsAndWheelDrops => {
+                    buf[0] = 0;
+                    1
+                }
+                PacketId::ChargingState => {
+                    buf[0] = 2; // full charging
+                    1
+                }
+                PacketId::Voltage => {
+                    buf[..2].copy_from_slice(&self.voltage_mv.to_be_bytes());
+                    2
+                }
+                PacketId::BatteryCharge => {
+                    buf[..2].copy_from_slice(&self.battery_mah.to_be_bytes());
+                    2
+                }
+                PacketId::Distance => {
+                    let bytes = self.distance_mm.to_be_bytes();
+                    self.distance_mm = 0; // reported since last read
+                    buf[..2].copy_from_slice(&bytes);
+                    2
+                }
+                PacketId::Angle => {
+                    let bytes = self.angle_deg.to_be_bytes();
+                    self.angle_deg = 0; // reported since last read
+                    buf[..2].copy_from_slice(&bytes);
+                    2
+                }
+            }
+        }
+
+        /// Number of fixed argument bytes an opcode takes, or `None` for the
+        /// variable-length opcodes handled specially.
+        fn fixed_args(opcode: u8) -> Option<usize> {
+            match opcode {
+                128 | 131 | 132 | 135 | 136 | 173 => Some(0),
+                138 | 141 | 142 | 150 => Some(1),
+                144 | 139 => Some(3),
+                137 | 145 | 146 | 164 => Some(4),
+                _ => None,
+            }
+        }
+
+        /// Minimum mode required for an opcode to be accepted.
+        fn required_mode(opcode: u8) -> SimMode {
+            match opcode {
+                // Actuator and song-playback commands need Safe or better.
+                137 | 138 | 139 | 141 | 145 | 146 | 164 => SimMode::Safe,
+                // Direct PWM of the cleaning motors is Full only.
+                144 => SimMode::Full,
+                // Everything else (mode changes, songs, queries, streams) is
+                // accepted from Passive upward.
+                _ => SimMode::Passive,
+            }
+        }
+
+        /// Feeds one received byte into the command parser.
+        fn feed(&mut self, byte: u8) {
+            let opcode = match self.opcode {
+                Some(opcode) => opcode,
+                None => {
+                    self.opcode = Some(byte);
+                    self.args_len = 0;
+                    if self.ready(byte) {
+                        self.execute();
+                    }
+                    return;
+                }
+            };
+            self.args[self.args_len] = byte;
+            self.args_len += 1;
+            if self.ready(opcode) {
+                self.execute();
+            }
+        }
+
+        /// Returns `true` once all arguments for `opcode` have been collected.
+        fn ready(&self, opcode: u8) -> bool {
+            if let Some(fixed) = Self::fixed_args(opcode) {
+                return self.args_len >= fixed;
+            }
+            match opcode {
+                // [slot, count, count*2 note bytes]
+                140 => self.args_len >= 2 && self.args_len >= 2 + self.args[1] as usize * 2,
+                // [N, id1..idN]
+                148 | 149 => self.args_len >= 1 && self.args_len >= 1 + self.args[0] as usize,
+                // Unknown opcode: treat as argument-less so we resynchronise.
+                _ => true,
+            }
+        }
+
+        /// Applies a fully received command, if it is legal for the current mode.
+        fn execute(&mut self) {
+            let opcode = self.opcode.take().unwrap();
+
+            // Mode changes are always processed.
+            match opcode {
+                128 => self.mode = SimMode::Passive,
+                131 => self.mode = SimMode::Safe,
+                132 => self.mode = SimMode::Full,
+                173 => self.mode = SimMode::Off,
+                _ => {}
+            }
+
+            // Reject anything the current mode does not permit.
+            if self.mode < Self::required_mode(opcode) {
+                return;
+            }
+
+            match opcode {
+                140 => {
+                    let slot = (self.args[0] & 0x03) as usize;
+                    let count = self.args[1] as usize;
+                    self.song_len[slot] = count as u8;
+                    let bytes = (count * 2).min(32);
+                    self.songs[slot][..bytes].copy_from_slice(&self.args[2..2 + bytes]);
+                }
+                142 => {
+                    if let Some(packet) = PacketId::from_id(self.args[0]) {
+                        let mut buf = [0u8; 2];
+                        let len = self.encode_packet(packet, &mut buf);
+                        self.push_out(&buf[..len]);
+                    }
+                }
+                149 => {
+                    let count = self.args[0] as usize;
+                    for index in 0..count {
+                        if let Some(packet) = PacketId::from_id(self.args[1 + index]) {
+                            let mut buf = [0u8; 2];
+                            let len = self.encode_packet(packet, &mut buf);
+                            self.push_out(&buf[..len]);
+                        }
+                    }
+                }
+                148 => {
+                    let count = (self.args[0] as usize).min(STREAM_LEN);
+                    self.stream_ids[..count].copy_from_slice(&self.args[1..1 + count]);
+                    self.stream_count = count;
+                    self.stream_paused = false;
+                }
+                150 => self.stream_paused = self.args[0] == 0,
+                // Remaining accepted commands have no observable reply.
+                _ => {}
+            }
+        }
+
+        /// Appends bytes to the response FIFO, dropping any that do not fit.
+        fn push_out(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                let next = (self.tail + 1) % OUT_LEN;
+                if next == self.head {
+                    break;
+                }
+                self.out[self.tail] = *byte;
+                self.tail = next;
+            }
+        }
+    }
+
+    impl Default for RumbaSimulator {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Write<u8> for RumbaSimulator {
+        type Error = core::convert::Infallible;
+
+        fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+            self.feed(word);
+            Ok(())
+        }
+
+        fn flush(&mut self) -> nb::Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl Read<u8> for RumbaSimulator {
+        type Error = core::convert::Infallible;
+
+        fn read(&mut self) -> nb::Result<u8, Self::Error> {
+            if self.head == self.tail {
+                return Err(nb::Error::WouldBlock);
+            }
+            let byte = self.out[self.head];
+            self.head = (self.head + 1) % OUT_LEN;
+            Ok(byte)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prelude::U16Ext;
+    use super::*;
+
+    extern crate std;
+
+    use std::assert_eq;
+    use std::cell::RefCell;
+    use std::vec;
+    use std::vec::Vec;
+
+    struct MockSerial<'a> {
+        data: &'a RefCell<Vec<u8>>,
+    }
+
+    impl<'a> Write<u8> for MockSerial<'a> {
+        type Error = core::convert::Infallible;
+        fn write(&mut self, value: u8) -> nb::Result<(), Self::Error> {
+            self.data.borrow_mut().push(value);
+            Ok(())
+        }
+        fn flush(&mut self) -> nb::Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    impl<'a> Read<u8> for MockSerial<'a> {
+        type Error = core::convert::Infallible;
         fn read(&mut self) -> nb::Result<u8, Self::Error> {
             Ok(0)
         }
@@ -340,7 +2352,7 @@ mod tests {
                 },
             ];
 
-            rumba.send_song(SongSlot::First, &song).unwrap();
+            rumba.send_song(SongSlot::First, &song).ok().unwrap();
             assert_eq!(*vector.borrow(), vec![140, 0, 2, 86, 64, 74, 64]);
             vector.borrow_mut().clear();
         }
@@ -385,6 +2397,71 @@ mod tests {
 
     #[test]
     fn note_duration_from_ms() {
-        assert_eq!(16u16.ms().ticks, 1);
+        assert_eq!(16u16.ms().ticks(), 1);
+    }
+
+    #[test]
+    fn note_duration_rounds_to_nearest_tick() {
+        // 1000 ms is 64 ticks exactly; 1008 ms is 64.512 ticks, which rounds up to
+        // 65 where the old truncating formula would have floored it to 64.
+        assert_eq!(1000u16.ms().ticks(), 64);
+        assert_eq!(1008u16.ms().ticks(), 65);
+    }
+
+    #[test]
+    fn note_duration_saturates_and_reports_over_long() {
+        // 255 ticks is the maximum; anything longer saturates on the wire but is
+        // rejected by the checked constructor.
+        assert_eq!(60_000u16.ms().ticks(), 255);
+        assert!(NoteDuration::from_ms(4000).is_err());
+        assert!(NoteDuration::from_ms(3900).is_ok());
+    }
+
+    #[test]
+    fn send_song_rejects_over_long_slot() {
+        let vector = std::cell::RefCell::new(std::vec![]);
+        let serial = MockSerial { data: &vector };
+
+        let mut rumba = Rumba::new(serial).into_passive();
+        vector.borrow_mut().clear();
+
+        let notes: Vec<Note> = (0..17)
+            .map(|_| Note {
+                name: NoteName::C,
+                octave: NoteOctave::OneLined,
+                duration: 16u16.ms(),
+            })
+            .collect();
+        assert!(matches!(
+            rumba.send_song(SongSlot::First, &notes),
+            Err(SongError::TooManyNotes)
+        ));
+        // Nothing should have been written for the rejected song.
+        assert_eq!(*vector.borrow(), vec![]);
+    }
+
+    #[test]
+    fn simulator_answers_battery_query() {
+        use super::simulator::RumbaSimulator;
+
+        let rumba = Rumba::new(RumbaSimulator::new());
+        let mut rumba = rumba.into_passive();
+        let data = rumba.sensor(PacketId::BatteryCharge);
+        assert!(matches!(data, Ok(SensorData::BatteryCharge(2600))));
+    }
+
+    #[test]
+    fn simulator_answers_query_list() {
+        use super::simulator::RumbaSimulator;
+
+        let rumba = Rumba::new(RumbaSimulator::new());
+        let mut rumba = rumba.into_passive();
+        let mut out = [SensorData::Voltage(0); 2];
+        rumba
+            .sensors(&[PacketId::Voltage, PacketId::BatteryCharge], &mut out)
+            .ok()
+            .unwrap();
+        assert_eq!(out[0], SensorData::Voltage(16000));
+        assert_eq!(out[1], SensorData::BatteryCharge(2600));
     }
 }